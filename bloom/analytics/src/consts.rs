@@ -1,8 +1,110 @@
+//! Validation limits for ingested events.
+//!
+//! These used to be hardcoded ceilings; they're now the *defaults* backing
+//! [`Limits`], which a deployment can override at startup (e.g. to raise
+//! the property-size cap on a high-volume instance) without a rebuild.
+
+/// Default max length of a recorded `User-Agent` header.
 pub const USER_AGENT_MAX_LENGTH: usize = 512;
+/// Default max length of a page name.
 pub const PAGE_NAME_MAX_LENGTH: usize = 256;
+/// Default max length of an event name.
 pub const EVENT_NAME_MAX_LENGTH: usize = 256;
+/// Default max length of a referrer URL.
 pub const REFERRER_MAX_LENGTH: usize = 512;
+/// Default max length of a single event property value.
 pub const EVENT_PROPERTY_MAX_LENGTH: usize = 1024;
+/// Default max total size of an event's properties, in bytes.
 pub const EVENT_PROPERTIES_MAX_SIZE: usize = 20000; // 20KB
+/// Default max length of an event URL.
 pub const EVENT_URLMAX_LENGTH: usize = 2048;
-pub const EVENT_USER_ID_MAX_LENGTH: usize = 256;
\ No newline at end of file
+/// Default max length of an event's user ID.
+pub const EVENT_USER_ID_MAX_LENGTH: usize = 256;
+
+/// Runtime-configurable event-validation limits.
+///
+/// Construct with [`Limits::from_env`] at startup; pass the resulting
+/// value down through the ingest/validation path instead of reaching for
+/// the bare consts above, so a deployment can override any one of them
+/// (e.g. `ANALYTICS_EVENT_PROPERTIES_MAX_SIZE=102400`) without a rebuild.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Limits {
+    pub user_agent_max_length: usize,
+    pub page_name_max_length: usize,
+    pub event_name_max_length: usize,
+    pub referrer_max_length: usize,
+    pub event_property_max_length: usize,
+    pub event_properties_max_size: usize,
+    pub event_url_max_length: usize,
+    pub event_user_id_max_length: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            user_agent_max_length: USER_AGENT_MAX_LENGTH,
+            page_name_max_length: PAGE_NAME_MAX_LENGTH,
+            event_name_max_length: EVENT_NAME_MAX_LENGTH,
+            referrer_max_length: REFERRER_MAX_LENGTH,
+            event_property_max_length: EVENT_PROPERTY_MAX_LENGTH,
+            event_properties_max_size: EVENT_PROPERTIES_MAX_SIZE,
+            event_url_max_length: EVENT_URLMAX_LENGTH,
+            event_user_id_max_length: EVENT_USER_ID_MAX_LENGTH,
+        }
+    }
+}
+
+impl Limits {
+    /// Builds the defaults, then overrides each field from its
+    /// `ANALYTICS_*_MAX_*` environment variable when present and parseable.
+    pub fn from_env() -> Self {
+        let mut limits = Limits::default();
+        limits.user_agent_max_length = env_override("ANALYTICS_USER_AGENT_MAX_LENGTH", limits.user_agent_max_length);
+        limits.page_name_max_length = env_override("ANALYTICS_PAGE_NAME_MAX_LENGTH", limits.page_name_max_length);
+        limits.event_name_max_length = env_override("ANALYTICS_EVENT_NAME_MAX_LENGTH", limits.event_name_max_length);
+        limits.referrer_max_length = env_override("ANALYTICS_REFERRER_MAX_LENGTH", limits.referrer_max_length);
+        limits.event_property_max_length =
+            env_override("ANALYTICS_EVENT_PROPERTY_MAX_LENGTH", limits.event_property_max_length);
+        limits.event_properties_max_size =
+            env_override("ANALYTICS_EVENT_PROPERTIES_MAX_SIZE", limits.event_properties_max_size);
+        limits.event_url_max_length = env_override("ANALYTICS_EVENT_URL_MAX_LENGTH", limits.event_url_max_length);
+        limits.event_user_id_max_length =
+            env_override("ANALYTICS_EVENT_USER_ID_MAX_LENGTH", limits.event_user_id_max_length);
+        limits
+    }
+}
+
+fn env_override(key: &str, default: usize) -> usize {
+    std::env::var(key)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_default_matches_consts() {
+        let limits = Limits::default();
+        assert_eq!(limits.event_properties_max_size, EVENT_PROPERTIES_MAX_SIZE);
+        assert_eq!(limits.user_agent_max_length, USER_AGENT_MAX_LENGTH);
+    }
+
+    #[test]
+    fn test_env_override_falls_back_on_missing_or_bad_value() {
+        assert_eq!(env_override("ANALYTICS_DOES_NOT_EXIST", 42), 42);
+
+        std::env::set_var("ANALYTICS_TEST_BAD_VALUE", "not a number");
+        assert_eq!(env_override("ANALYTICS_TEST_BAD_VALUE", 42), 42);
+        std::env::remove_var("ANALYTICS_TEST_BAD_VALUE");
+    }
+
+    #[test]
+    fn test_env_override_applies_valid_value() {
+        std::env::set_var("ANALYTICS_TEST_GOOD_VALUE", "99");
+        assert_eq!(env_override("ANALYTICS_TEST_GOOD_VALUE", 42), 99);
+        std::env::remove_var("ANALYTICS_TEST_GOOD_VALUE");
+    }
+}
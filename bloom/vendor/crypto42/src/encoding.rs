@@ -0,0 +1,59 @@
+//! A tiny unpadded standard-alphabet base64 codec, used by this crate's
+//! PHC-string encoders/parsers (`kdf::argon2`, `kdf::pbkdf2`) so they
+//! don't each need their own copy or an external `base64` dependency.
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub fn base64_encode(data: &[u8]) -> String {
+    let mut out = String::new();
+    for chunk in data.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = *chunk.get(1).unwrap_or(&0);
+        let b2 = *chunk.get(2).unwrap_or(&0);
+
+        out.push(ALPHABET[(b0 >> 2) as usize] as char);
+        out.push(ALPHABET[(((b0 & 0x03) << 4) | (b1 >> 4)) as usize] as char);
+        if chunk.len() > 1 {
+            out.push(ALPHABET[(((b1 & 0x0f) << 2) | (b2 >> 6)) as usize] as char);
+        }
+        if chunk.len() > 2 {
+            out.push(ALPHABET[(b2 & 0x3f) as usize] as char);
+        }
+    }
+    out
+}
+
+pub fn base64_decode(encoded: &str) -> Option<Vec<u8>> {
+    let lookup = |c: u8| ALPHABET.iter().position(|&b| b == c).map(|i| i as u8);
+
+    let chars: Vec<u8> = encoded.bytes().collect();
+    let mut out = Vec::with_capacity(chars.len() * 3 / 4);
+
+    for chunk in chars.chunks(4) {
+        let vals: Vec<u8> = chunk.iter().map(|&c| lookup(c)).collect::<Option<_>>()?;
+        out.push((vals[0] << 2) | (vals.get(1).unwrap_or(&0) >> 4));
+        if vals.len() > 2 {
+            out.push((vals[1] << 4) | (vals[2] >> 2));
+        }
+        if vals.len() > 3 {
+            out.push((vals[2] << 6) | vals[3]);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let data = b"a salt-shaped byte string!";
+        assert_eq!(base64_decode(&base64_encode(data)).unwrap(), data);
+    }
+
+    #[test]
+    fn test_round_trip_empty() {
+        assert_eq!(base64_decode(&base64_encode(b"")).unwrap(), b"");
+    }
+}
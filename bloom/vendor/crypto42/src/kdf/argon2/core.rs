@@ -0,0 +1,473 @@
+//! The pure-Rust Argon2 reference algorithm (RFC 9106), used where the
+//! variant or lane configuration requested isn't one libsodium's bound
+//! `crypto_pwhash_argon2id` surface can produce - today, Argon2i/Argon2d
+//! addressing.
+//!
+//! Lanes within a slice are independent of each other by construction
+//! (the only cross-lane references a slice's fill step is allowed to make
+//! land in already-finished slices), so [`fill_memory`] advances all
+//! lanes of a slice concurrently, synchronizing at each slice boundary,
+//! and caps how many lanes run at once via `Config::max_threads`.
+
+use super::blake2b;
+use super::variant::Variant;
+
+const BLOCK_WORDS: usize = 128;
+const VERSION: u32 = 0x13;
+const SYNC_POINTS: u32 = 4;
+
+type Block = [u64; BLOCK_WORDS];
+
+/// Parameters controlling an Argon2 derivation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Config {
+    pub variant: Variant,
+    pub memory_kib: u32,
+    pub passes: u32,
+    pub lanes: u32,
+    /// Caps how many lanes are advanced concurrently per slice. `0` means
+    /// "one worker per lane" (no cap) - the right default when a server
+    /// is already sizing `lanes` to the cores it wants to spend on a
+    /// single derivation.
+    pub max_threads: usize,
+}
+
+/// Derives `out_len` bytes from `password` and `salt` per `config`.
+pub fn derive(password: &[u8], salt: &[u8], config: &Config, out_len: usize) -> Vec<u8> {
+    let lane_length = lane_length(config);
+    let total_blocks = lane_length * config.lanes as usize;
+
+    let h0 = initial_hash(password, salt, config, out_len);
+    let mut memory = vec![[0u64; BLOCK_WORDS]; total_blocks];
+
+    for lane in 0..config.lanes as usize {
+        memory[lane * lane_length] = block_from_h0(&h0, 0, lane);
+        memory[lane * lane_length + 1] = block_from_h0(&h0, 1, lane);
+    }
+
+    fill_memory(&mut memory, config, lane_length);
+
+    let mut final_block = memory[lane_length - 1];
+    for lane in 1..config.lanes as usize {
+        xor_into(&mut final_block, &memory[lane * lane_length + lane_length - 1]);
+    }
+
+    blake2b::hash_long(&block_to_bytes(&final_block), out_len)
+}
+
+fn lane_length(config: &Config) -> usize {
+    let segments_per_lane = SYNC_POINTS as usize;
+    let blocks_per_lane = (config.memory_kib as usize) / (config.lanes as usize).max(1);
+    let segment_length = (blocks_per_lane / segments_per_lane).max(2);
+    segment_length * segments_per_lane
+}
+
+fn initial_hash(password: &[u8], salt: &[u8], config: &Config, out_len: usize) -> Vec<u8> {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&config.lanes.to_le_bytes());
+    buf.extend_from_slice(&(out_len as u32).to_le_bytes());
+    buf.extend_from_slice(&config.memory_kib.to_le_bytes());
+    buf.extend_from_slice(&config.passes.to_le_bytes());
+    buf.extend_from_slice(&VERSION.to_le_bytes());
+    buf.extend_from_slice(&(config.variant.type_code() as u32).to_le_bytes());
+
+    buf.extend_from_slice(&(password.len() as u32).to_le_bytes());
+    buf.extend_from_slice(password);
+    buf.extend_from_slice(&(salt.len() as u32).to_le_bytes());
+    buf.extend_from_slice(salt);
+    // No secret key or associated data in this crate's surface.
+    buf.extend_from_slice(&0u32.to_le_bytes());
+    buf.extend_from_slice(&0u32.to_le_bytes());
+
+    blake2b::hash(&buf, 64)
+}
+
+fn block_from_h0(h0: &[u8], block_index: u32, lane: usize) -> Block {
+    let mut input = Vec::with_capacity(h0.len() + 8);
+    input.extend_from_slice(h0);
+    input.extend_from_slice(&block_index.to_le_bytes());
+    input.extend_from_slice(&(lane as u32).to_le_bytes());
+    bytes_to_block(&blake2b::hash_long(&input, 1024))
+}
+
+/// Fills the remaining memory blocks pass by pass, slice by slice,
+/// running up to `Config::max_threads` lanes of each slice concurrently.
+fn fill_memory(memory: &mut [Block], config: &Config, lane_length: usize) {
+    let segment_length = lane_length / SYNC_POINTS as usize;
+    let worker_cap = if config.max_threads == 0 {
+        config.lanes as usize
+    } else {
+        config.max_threads.min(config.lanes as usize).max(1)
+    };
+
+    let shared = SharedBlocks::new(memory);
+
+    for pass in 0..config.passes {
+        for slice in 0..SYNC_POINTS {
+            let mut remaining: Vec<u32> = (0..config.lanes).collect();
+            while !remaining.is_empty() {
+                let batch: Vec<u32> = remaining.drain(..worker_cap.min(remaining.len())).collect();
+                std::thread::scope(|scope| {
+                    for lane in batch {
+                        let shared = &shared;
+                        scope.spawn(move || {
+                            fill_segment(shared, config, lane_length, segment_length, pass, slice, lane);
+                        });
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// A raw, unsynchronized view over the whole memory matrix, shared across
+/// the worker threads that advance one lane each within a slice.
+///
+/// Soundness: within a given `(pass, slice)`, every thread is assigned a
+/// distinct `lane` and only ever *writes* blocks in that lane's own
+/// `lane_length`-sized region (see [`fill_segment`]'s `cur_index`).
+/// Reads can land in any lane's region, but [`index_alpha`] only ever
+/// resolves references into already-finished slices of other lanes (or
+/// the already-finished portion of the current slice for the lane being
+/// written to), never into a slice another thread is concurrently
+/// filling. That invariant - not the type system - is what makes the
+/// interior mutability below race-free.
+struct SharedBlocks {
+    ptr: *mut Block,
+    len: usize,
+}
+
+unsafe impl Sync for SharedBlocks {}
+
+impl SharedBlocks {
+    fn new(memory: &mut [Block]) -> Self {
+        SharedBlocks { ptr: memory.as_mut_ptr(), len: memory.len() }
+    }
+
+    fn read(&self, index: usize) -> Block {
+        debug_assert!(index < self.len);
+        unsafe { *self.ptr.add(index) }
+    }
+
+    fn write(&self, index: usize, value: Block) {
+        debug_assert!(index < self.len);
+        unsafe { *self.ptr.add(index) = value };
+    }
+}
+
+fn fill_segment(
+    memory: &SharedBlocks,
+    config: &Config,
+    lane_length: usize,
+    segment_length: usize,
+    pass: u32,
+    slice: u32,
+    lane: u32,
+) {
+    let independent = config.variant.uses_independent_addressing(pass, slice);
+    let mut addr_stream = independent.then(|| {
+        AddressStream::new(config, pass, slice, lane, lane_length)
+    });
+
+    for index_in_segment in 0..segment_length {
+        let block_index = slice as usize * segment_length + index_in_segment;
+
+        // The address stream must advance in lockstep with
+        // `index_in_segment` even for the seed blocks skipped below -
+        // the reference algorithm indexes into it by position, not by
+        // how many blocks were actually written, so skipping a `next()`
+        // call here would desynchronize every later `(j1, j2)` in this
+        // segment.
+        let stream_next = addr_stream.as_mut().map(|stream| stream.next());
+
+        if pass == 0 && block_index < 2 {
+            continue;
+        }
+
+        let prev_index = if block_index == 0 { lane_length - 1 } else { block_index - 1 };
+        let prev = memory.read(lane as usize * lane_length + prev_index);
+
+        let (j1, j2) = match stream_next {
+            Some(pair) => pair,
+            None => (prev[0] as u32, (prev[0] >> 32) as u32),
+        };
+
+        let ref_lane = if pass == 0 && slice == 0 { lane } else { j2 % config.lanes.max(1) };
+        let ref_index = index_alpha(
+            pass,
+            slice,
+            block_index,
+            index_in_segment,
+            segment_length,
+            lane_length,
+            ref_lane == lane,
+            j1,
+        );
+
+        let ref_block = memory.read(ref_lane as usize * lane_length + ref_index);
+        let cur_index = lane as usize * lane_length + block_index;
+        let with_xor = pass > 0;
+        let prev_value = memory.read(cur_index);
+        let new_block = compress(&prev, &ref_block, with_xor.then_some(prev_value));
+        memory.write(cur_index, new_block);
+    }
+}
+
+/// Argon2's `index_alpha`: maps `(J1, reference lane)` to a concrete
+/// block index within that lane, restricted to blocks already computed.
+fn index_alpha(
+    pass: u32,
+    slice: u32,
+    block_index: usize,
+    index_in_segment: usize,
+    segment_length: usize,
+    lane_length: usize,
+    same_lane: bool,
+    j1: u32,
+) -> usize {
+    let reference_area_size = if pass == 0 {
+        if slice == 0 || same_lane {
+            slice as usize * segment_length + index_in_segment - 1
+        } else {
+            slice as usize * segment_length - usize::from(index_in_segment == 0)
+        }
+    } else if same_lane {
+        lane_length - segment_length + index_in_segment - 1
+    } else {
+        lane_length - segment_length - usize::from(index_in_segment == 0)
+    };
+
+    let reference_area_size = reference_area_size.max(1) as u64;
+    let j1 = j1 as u64;
+    let relative = j1 * j1 >> 32;
+    let relative = reference_area_size - 1 - ((reference_area_size * relative) >> 32);
+
+    let start_position = if pass != 0 && slice != SYNC_POINTS - 1 {
+        (slice as usize + 1) * segment_length
+    } else {
+        0
+    };
+
+    (start_position + relative as usize) % lane_length
+}
+
+struct AddressStream {
+    counter_block: Block,
+    input_block: Block,
+    position: usize,
+    buffer: [u64; BLOCK_WORDS],
+}
+
+impl AddressStream {
+    fn new(config: &Config, pass: u32, slice: u32, lane: u32, lane_length: usize) -> Self {
+        let mut input_block = [0u64; BLOCK_WORDS];
+        input_block[0] = pass as u64;
+        input_block[1] = lane as u64;
+        input_block[2] = slice as u64;
+        input_block[3] = (lane_length * config.lanes as usize) as u64;
+        input_block[4] = config.passes as u64;
+        input_block[5] = config.variant.type_code();
+        input_block[6] = 0;
+
+        let mut stream = AddressStream {
+            counter_block: [0u64; BLOCK_WORDS],
+            input_block,
+            position: 0,
+            buffer: [0u64; BLOCK_WORDS],
+        };
+        stream.refill();
+        stream
+    }
+
+    fn refill(&mut self) {
+        self.input_block[6] += 1;
+        let zero = [0u64; BLOCK_WORDS];
+        self.counter_block = compress(&zero, &self.input_block, None);
+        self.buffer = compress(&zero, &self.counter_block, None);
+        self.position = 0;
+    }
+
+    fn next(&mut self) -> (u32, u32) {
+        if self.position == BLOCK_WORDS {
+            self.refill();
+        }
+        let word = self.buffer[self.position];
+        self.position += 1;
+        (word as u32, (word >> 32) as u32)
+    }
+}
+
+/// Argon2's compression function `G`: a BLAKE2b-derived permutation
+/// applied first to the rows, then the columns, of the 8x16 matrix
+/// formed by `prev XOR ref`, with the result XORed back over that same
+/// input (and, for passes after the first, over the block being
+/// overwritten).
+fn compress(prev: &Block, reference: &Block, overwrite: Option<Block>) -> Block {
+    let mut r = [0u64; BLOCK_WORDS];
+    for i in 0..BLOCK_WORDS {
+        r[i] = prev[i] ^ reference[i];
+    }
+
+    let mut z = r;
+    for row in 0..8 {
+        permute_row(&mut z, row * 16);
+    }
+    for col in 0..8 {
+        permute_column(&mut z, col * 2);
+    }
+
+    let mut out = [0u64; BLOCK_WORDS];
+    for i in 0..BLOCK_WORDS {
+        out[i] = z[i] ^ r[i];
+        if let Some(prior) = overwrite {
+            out[i] ^= prior[i];
+        }
+    }
+    out
+}
+
+fn blamka(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(2u64.wrapping_mul(lo(v[a])).wrapping_mul(lo(v[b])));
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = v[c].wrapping_add(v[d]).wrapping_add(2u64.wrapping_mul(lo(v[c])).wrapping_mul(lo(v[d])));
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(2u64.wrapping_mul(lo(v[a])).wrapping_mul(lo(v[b])));
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]).wrapping_add(2u64.wrapping_mul(lo(v[c])).wrapping_mul(lo(v[d])));
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+fn lo(x: u64) -> u64 {
+    x & 0xFFFF_FFFF
+}
+
+fn round(v: &mut [u64; 16]) {
+    blamka(v, 0, 4, 8, 12);
+    blamka(v, 1, 5, 9, 13);
+    blamka(v, 2, 6, 10, 14);
+    blamka(v, 3, 7, 11, 15);
+    blamka(v, 0, 5, 10, 15);
+    blamka(v, 1, 6, 11, 12);
+    blamka(v, 2, 7, 8, 13);
+    blamka(v, 3, 4, 9, 14);
+}
+
+fn permute_row(z: &mut Block, offset: usize) {
+    let mut v: [u64; 16] = z[offset..offset + 16].try_into().unwrap();
+    round(&mut v);
+    z[offset..offset + 16].copy_from_slice(&v);
+}
+
+fn permute_column(z: &mut Block, col_pair: usize) {
+    let mut v = [0u64; 16];
+    for row in 0..8 {
+        v[row * 2] = z[row * 16 + col_pair];
+        v[row * 2 + 1] = z[row * 16 + col_pair + 1];
+    }
+    round(&mut v);
+    for row in 0..8 {
+        z[row * 16 + col_pair] = v[row * 2];
+        z[row * 16 + col_pair + 1] = v[row * 2 + 1];
+    }
+}
+
+fn xor_into(dst: &mut Block, src: &Block) {
+    for i in 0..BLOCK_WORDS {
+        dst[i] ^= src[i];
+    }
+}
+
+fn bytes_to_block(bytes: &[u8]) -> Block {
+    let mut block = [0u64; BLOCK_WORDS];
+    for (word, chunk) in block.iter_mut().zip(bytes.chunks_exact(8)) {
+        *word = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    block
+}
+
+fn block_to_bytes(block: &Block) -> Vec<u8> {
+    let mut out = Vec::with_capacity(BLOCK_WORDS * 8);
+    for word in block.iter() {
+        out.extend_from_slice(&word.to_le_bytes());
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_derive_is_deterministic() {
+        let config = Config { variant: Variant::Argon2id, memory_kib: 8, passes: 1, lanes: 1, max_threads: 0 };
+        let a = derive(b"password", b"somesalt", &config, 32);
+        let b = derive(b"password", b"somesalt", &config, 32);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_variants_diverge() {
+        let base = Config { variant: Variant::Argon2id, memory_kib: 8, passes: 1, lanes: 1, max_threads: 0 };
+        let d = Config { variant: Variant::Argon2d, ..base };
+        let i = Config { variant: Variant::Argon2i, ..base };
+
+        let id_out = derive(b"password", b"somesalt", &base, 32);
+        let d_out = derive(b"password", b"somesalt", &d, 32);
+        let i_out = derive(b"password", b"somesalt", &i, 32);
+
+        assert_ne!(id_out, d_out);
+        assert_ne!(id_out, i_out);
+        assert_ne!(d_out, i_out);
+    }
+
+    #[test]
+    fn test_different_salt_diverges() {
+        let config = Config { variant: Variant::Argon2id, memory_kib: 8, passes: 1, lanes: 1, max_threads: 0 };
+        let a = derive(b"password", b"somesalt", &config, 32);
+        let b = derive(b"password", b"othersalt", &config, 32);
+        assert_ne!(a, b);
+    }
+
+    /// Known-answer tests cross-checked against libsodium's
+    /// `crypto_pwhash` for the same password/salt/parameters (libsodium
+    /// doesn't expose Argon2d, so only Argon2i/Argon2id are covered here).
+    #[test]
+    fn test_kat_argon2id_matches_libsodium() {
+        let config = Config { variant: Variant::Argon2id, memory_kib: 65536, passes: 2, lanes: 1, max_threads: 0 };
+        let salt: Vec<u8> = (1..=16).collect();
+        let out = derive(b"hunter2", &salt, &config, 32);
+        assert_eq!(
+            out,
+            hex("238ae6de7b8fa7536fe1a00696ba539ed214c220870c06ae57c55fc0f61f622f")
+        );
+    }
+
+    #[test]
+    fn test_kat_argon2id_small_params_matches_libsodium() {
+        let config = Config { variant: Variant::Argon2id, memory_kib: 8, passes: 3, lanes: 1, max_threads: 0 };
+        let salt: Vec<u8> = (1..=16).collect();
+        let out = derive(b"hunter2", &salt, &config, 32);
+        assert_eq!(
+            out,
+            hex("2a6d4ce6d2b53c2c9b192be845e0229e2fc8ab35a1c8129ef0c57357fc323bda")
+        );
+    }
+
+    #[test]
+    fn test_kat_argon2i_matches_libsodium() {
+        let config = Config { variant: Variant::Argon2i, memory_kib: 8, passes: 3, lanes: 1, max_threads: 0 };
+        let salt: Vec<u8> = (1..=16).collect();
+        let out = derive(b"hunter2", &salt, &config, 32);
+        assert_eq!(
+            out,
+            hex("b890620c65dfb308f4e1adb6894cb32bd3a404004d04bbbe3e39ce0bc942cbfc")
+        );
+    }
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+}
@@ -0,0 +1,220 @@
+//! Variant-selectable Argon2 (`Argon2d`/`Argon2i`/`Argon2id`), on top of
+//! the pure-Rust [`core`] engine.
+//!
+//! `kdf::argon2id` remains the fast path for the common case (libsodium's
+//! native Argon2id binding); reach for this module when a caller
+//! explicitly needs Argon2i's data-independent addressing or Argon2d's
+//! TMTO resistance, or needs to verify a stored hash of unknown variant.
+
+mod blake2b;
+mod core;
+mod variant;
+
+use crate::encoding::{base64_decode, base64_encode};
+use crate::Error;
+pub use variant::Variant;
+
+const SALTBYTES: usize = 16;
+
+/// A random salt used to derive a hash from a password.
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct Salt(pub [u8; SALTBYTES]);
+
+/// Generates a random [`Salt`].
+pub fn gen_salt() -> Salt {
+    let mut salt = [0; SALTBYTES];
+    crate::randombytes::randombytes_into(&mut salt);
+    Salt(salt)
+}
+
+/// An encoded PHC-string hash (e.g. `$argon2id$v=19$m=65536,t=2,p=1$...`).
+#[derive(Clone, Eq, PartialEq)]
+pub struct HashedPassword(pub String);
+
+impl HashedPassword {
+    /// The variant (`Argon2d`/`Argon2i`/`Argon2id`) this hash was produced
+    /// with, or `None` if it isn't a well-formed Argon2 PHC string.
+    pub fn get_variant(&self) -> Option<Variant> {
+        parse_phc(&self.0).map(|(variant, ..)| variant)
+    }
+
+    /// The iteration count (`t=`) embedded in this hash.
+    pub fn get_cost(&self) -> Option<u32> {
+        parse_phc(&self.0).map(|(_, t_cost, ..)| t_cost)
+    }
+
+    /// The memory cost in KiB (`m=`) embedded in this hash.
+    pub fn get_memory(&self) -> Option<u32> {
+        parse_phc(&self.0).map(|(_, _, m_cost, ..)| m_cost)
+    }
+
+    /// The lane count (`p=`) embedded in this hash.
+    pub fn get_parallelism(&self) -> Option<u32> {
+        parse_phc(&self.0).map(|(_, _, _, lanes, ..)| lanes)
+    }
+
+    /// The raw salt embedded in this hash.
+    pub fn get_salt(&self) -> Option<Vec<u8>> {
+        parse_phc(&self.0).map(|(_, _, _, _, salt, _)| salt)
+    }
+}
+
+/// Hashes `password` with a freshly generated salt under the given
+/// `variant`, `t_cost` (iterations) and `m_cost` (memory, in KiB).
+pub fn hash_password(
+    password: &[u8],
+    variant: Variant,
+    t_cost: u32,
+    m_cost: u32,
+    lanes: u32,
+) -> Result<HashedPassword, Error> {
+    hash_password_with_threads(password, variant, t_cost, m_cost, lanes, 0)
+}
+
+/// Like [`hash_password`], but caps the number of worker threads used to
+/// advance lanes concurrently (`0` means one worker per lane).
+pub fn hash_password_with_threads(
+    password: &[u8],
+    variant: Variant,
+    t_cost: u32,
+    m_cost: u32,
+    lanes: u32,
+    max_threads: usize,
+) -> Result<HashedPassword, Error> {
+    if t_cost == 0 || m_cost == 0 || lanes == 0 {
+        return Err(Error::Unknown);
+    }
+
+    let salt = gen_salt();
+    let config = core::Config { variant, memory_kib: m_cost, passes: t_cost, lanes, max_threads };
+    let hash = core::derive(password, &salt.0, &config, 32);
+
+    Ok(HashedPassword(encode_phc(variant, t_cost, m_cost, lanes, &salt.0, &hash)))
+}
+
+/// Verifies `password` against a previously-encoded [`HashedPassword`].
+pub fn verify_password(hashed: &HashedPassword, password: &[u8]) -> bool {
+    let Some((variant, t_cost, m_cost, lanes, salt, expected)) = parse_phc(&hashed.0) else {
+        return false;
+    };
+
+    let config = core::Config { variant, memory_kib: m_cost, passes: t_cost, lanes, max_threads: 0 };
+    let actual = core::derive(password, &salt, &config, expected.len());
+    crate::utils::memcmp(&actual, &expected)
+}
+
+/// Reports whether `hashed`'s embedded cost parameters are weaker than
+/// `target_ops`/`target_mem`, i.e. whether the caller should re-hash the
+/// password with the current policy before the session ends. A
+/// malformed hash is always reported as needing a rehash.
+pub fn needs_rehash(
+    hashed: &HashedPassword,
+    target_ops: crate::kdf::argon2id::OpsLimit,
+    target_mem: crate::kdf::argon2id::MemLimit,
+) -> bool {
+    match (hashed.get_cost(), hashed.get_memory()) {
+        (Some(t_cost), Some(m_cost)) => {
+            (t_cost as usize) < target_ops.0 || (m_cost as usize) * 1024 < target_mem.0
+        }
+        _ => true,
+    }
+}
+
+/// Like [`needs_rehash`], but additionally reports a rehash as needed
+/// when `hashed` was produced with a variant other than `target_variant`
+/// (e.g. upgrading old `Argon2i` hashes to `Argon2id`).
+pub fn needs_rehash_variant(hashed: &HashedPassword, target_variant: Variant) -> bool {
+    hashed.get_variant() != Some(target_variant)
+}
+
+fn encode_phc(variant: Variant, t_cost: u32, m_cost: u32, lanes: u32, salt: &[u8], hash: &[u8]) -> String {
+    format!(
+        "${}$v=19$m={},t={},p={}${}${}",
+        variant.phc_id(),
+        m_cost,
+        t_cost,
+        lanes,
+        base64_encode(salt),
+        base64_encode(hash),
+    )
+}
+
+fn parse_phc(encoded: &str) -> Option<(Variant, u32, u32, u32, Vec<u8>, Vec<u8>)> {
+    let mut parts = encoded.split('$');
+    debug_assert!(parts.next() == Some(""));
+
+    let variant = Variant::from_phc_id(parts.next()?)?;
+    let v_field = parts.next()?;
+    if !v_field.starts_with("v=") {
+        return None;
+    }
+
+    let params = parts.next()?;
+    let mut m_cost = None;
+    let mut t_cost = None;
+    let mut lanes = None;
+    for field in params.split(',') {
+        let (key, value) = field.split_once('=')?;
+        match key {
+            "m" => m_cost = value.parse().ok(),
+            "t" => t_cost = value.parse().ok(),
+            "p" => lanes = value.parse().ok(),
+            _ => {}
+        }
+    }
+
+    let salt = base64_decode(parts.next()?)?;
+    let hash = base64_decode(parts.next()?)?;
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Some((variant, t_cost?, m_cost?, lanes?, salt, hash))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hash_and_verify_each_variant() {
+        for variant in [Variant::Argon2d, Variant::Argon2i, Variant::Argon2id] {
+            let hashed = hash_password(b"hunter2", variant, 1, 8, 1).unwrap();
+            assert!(hashed.0.starts_with(&format!("${}$", variant.phc_id())));
+            assert!(verify_password(&hashed, b"hunter2"));
+            assert!(!verify_password(&hashed, b"wrong"));
+        }
+    }
+
+    #[test]
+    fn test_rejects_zero_cost() {
+        assert!(hash_password(b"hunter2", Variant::Argon2id, 0, 8, 1).is_err());
+        assert!(hash_password(b"hunter2", Variant::Argon2id, 1, 0, 1).is_err());
+    }
+
+    #[test]
+    fn test_accessors_round_trip_params() {
+        let hashed = hash_password(b"hunter2", Variant::Argon2i, 2, 16, 1).unwrap();
+        assert_eq!(hashed.get_variant(), Some(Variant::Argon2i));
+        assert_eq!(hashed.get_cost(), Some(2));
+        assert_eq!(hashed.get_memory(), Some(16));
+        assert_eq!(hashed.get_parallelism(), Some(1));
+        assert_eq!(hashed.get_salt().unwrap().len(), SALTBYTES);
+    }
+
+    #[test]
+    fn test_needs_rehash_on_weaker_params() {
+        use crate::kdf::argon2id::{MemLimit, OpsLimit};
+
+        let hashed = hash_password(b"hunter2", Variant::Argon2id, 1, 8, 1).unwrap();
+        assert!(needs_rehash(&hashed, OpsLimit(3), MemLimit(1 << 16)));
+        assert!(!needs_rehash(&hashed, OpsLimit(1), MemLimit(8 * 1024)));
+    }
+
+    #[test]
+    fn test_needs_rehash_variant_on_mismatch() {
+        let hashed = hash_password(b"hunter2", Variant::Argon2i, 1, 8, 1).unwrap();
+        assert!(needs_rehash_variant(&hashed, Variant::Argon2id));
+        assert!(!needs_rehash_variant(&hashed, Variant::Argon2i));
+    }
+}
@@ -0,0 +1,66 @@
+//! The three Argon2 variants, differing only in how the reference block
+//! index (`J1`/`J2`) used by each iteration is computed.
+
+/// Which Argon2 addressing scheme to use.
+///
+/// - `Argon2d` takes `J1`/`J2` from the previous block's first 64-bit
+///   word: fastest and most TMTO-resistant, but data-dependent addressing
+///   makes it unsuitable where cache-timing side channels matter.
+/// - `Argon2i` derives `J1`/`J2` from a separate pseudo-random stream
+///   (the compression function run over a counter block), independent of
+///   any secret data - the right choice for side-channel-sensitive
+///   environments, e.g. disk encryption passphrases handled on a shared
+///   host.
+/// - `Argon2id` hedges: Argon2i addressing for the first half of the
+///   first pass, Argon2d for everything after. This is the default
+///   recommended by the RFC, and the only variant this crate exposed
+///   before this module existed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Variant {
+    Argon2d,
+    Argon2i,
+    Argon2id,
+}
+
+impl Variant {
+    /// The `type` field value the Argon2 spec bakes into the seed block
+    /// and counter blocks.
+    pub(crate) fn type_code(self) -> u64 {
+        match self {
+            Variant::Argon2d => 0,
+            Variant::Argon2i => 1,
+            Variant::Argon2id => 2,
+        }
+    }
+
+    /// Whether the block at `(pass, slice)` should use Argon2i-style
+    /// (data-independent) addressing.
+    pub(crate) fn uses_independent_addressing(self, pass: u32, slice: u32) -> bool {
+        match self {
+            Variant::Argon2i => true,
+            Variant::Argon2d => false,
+            // First half of the first pass only - see module docs.
+            Variant::Argon2id => pass == 0 && slice < 2,
+        }
+    }
+
+    /// The PHC identifier used in the encoded hash string, e.g.
+    /// `argon2id` in `$argon2id$v=19$...`.
+    pub fn phc_id(self) -> &'static str {
+        match self {
+            Variant::Argon2d => "argon2d",
+            Variant::Argon2i => "argon2i",
+            Variant::Argon2id => "argon2id",
+        }
+    }
+
+    /// Parses a PHC identifier back into a [`Variant`].
+    pub fn from_phc_id(id: &str) -> Option<Variant> {
+        match id {
+            "argon2d" => Some(Variant::Argon2d),
+            "argon2i" => Some(Variant::Argon2i),
+            "argon2id" => Some(Variant::Argon2id),
+            _ => None,
+        }
+    }
+}
@@ -40,6 +40,70 @@ argon2_module!(
     crypto_pwhash_ALG_ARGON2ID13
 );
 
+/// A named security/performance tier, for callers who don't want to pick
+/// `OpsLimit`/`MemLimit` values themselves.
+///
+/// Mirrors libsodium's own INTERACTIVE/MODERATE/SENSITIVE tiers: roughly
+/// "fast enough to run on every login", "a deliberate few hundred ms",
+/// and "as expensive as a rarely-derived master key can afford to be".
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Level {
+    Interactive,
+    Moderate,
+    Sensitive,
+}
+
+impl Level {
+    fn limits(self) -> (OpsLimit, MemLimit) {
+        match self {
+            Level::Interactive => (OpsLimit::interactive(), MemLimit::interactive()),
+            Level::Moderate => (OpsLimit::moderate(), MemLimit::moderate()),
+            Level::Sensitive => (OpsLimit::sensitive(), MemLimit::sensitive()),
+        }
+    }
+}
+
+impl OpsLimit {
+    /// Cost suitable for interactive, online use (~2 passes).
+    pub fn interactive() -> Self {
+        OpsLimit(crypto_pwhash_argon2id_OPSLIMIT_INTERACTIVE as usize)
+    }
+
+    /// A deliberate, moderate cost for infrequent, non-blocking derivations.
+    pub fn moderate() -> Self {
+        OpsLimit(crypto_pwhash_argon2id_OPSLIMIT_MODERATE as usize)
+    }
+
+    /// Cost for highly sensitive, rarely-derived secrets (~4 passes).
+    pub fn sensitive() -> Self {
+        OpsLimit(crypto_pwhash_argon2id_OPSLIMIT_SENSITIVE as usize)
+    }
+}
+
+impl MemLimit {
+    /// Memory suitable for interactive, online use (~64 MiB).
+    pub fn interactive() -> Self {
+        MemLimit(crypto_pwhash_argon2id_MEMLIMIT_INTERACTIVE as usize)
+    }
+
+    /// A deliberate, moderate memory cost for infrequent derivations.
+    pub fn moderate() -> Self {
+        MemLimit(crypto_pwhash_argon2id_MEMLIMIT_MODERATE as usize)
+    }
+
+    /// Memory for highly sensitive, rarely-derived secrets (~1 GiB).
+    pub fn sensitive() -> Self {
+        MemLimit(crypto_pwhash_argon2id_MEMLIMIT_SENSITIVE as usize)
+    }
+}
+
+/// Hashes `password` at the vetted [`Level`] preset rather than requiring
+/// the caller to choose raw `OpsLimit`/`MemLimit` values.
+pub fn hash_password_with_level(password: &[u8], level: Level) -> Result<HashedPassword, crate::Error> {
+    let (ops, mem) = level.limits();
+    hash_password(password, ops, mem)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -603,4 +667,22 @@ mod test {
             "failed to verify_password password with hash"
         );
     }
+
+    #[test]
+    fn test_level_presets_are_ordered() {
+        assert!(OpsLimit::interactive().0 <= OpsLimit::moderate().0);
+        assert!(OpsLimit::moderate().0 <= OpsLimit::sensitive().0);
+        assert!(MemLimit::interactive().0 <= MemLimit::moderate().0);
+        assert!(MemLimit::moderate().0 <= MemLimit::sensitive().0);
+    }
+
+    #[test]
+    fn test_hash_password_with_level() {
+        let password = "Correct Horse Battery Staple";
+        let result = hash_password_with_level(password.as_bytes(), Level::Interactive);
+        assert!(result.is_ok(), "failed to hash password at Level::Interactive");
+
+        let out = result.unwrap();
+        assert!(verify_password(&out, password.as_bytes()));
+    }
 }
@@ -0,0 +1,228 @@
+//! The classic `$2a$`/`$2b$`/`$2x$`/`$2y$` bcrypt password hash, for
+//! interop with existing user databases that predate this crate's
+//! Argon2id default.
+//!
+//! This is a distinct construction from [`crate::kdf::bcrypt_pbkdf`]:
+//! both build on the same [`crate::kdf::blowfish`] eksblowfish core, but
+//! this one runs the expensive key schedule `2^cost` times over the
+//! password and salt directly and encrypts a fixed 24-byte constant,
+//! rather than stretching a passphrase into an arbitrary-length key.
+//! New designs should hash with `kdf::argon2id`; reach for this module
+//! only to verify, and gradually migrate off, hashes stored elsewhere.
+
+use crate::kdf::blowfish::Blowfish;
+
+const SALT_LEN: usize = 16;
+const CIPHERTEXT: &[u8; 24] = b"OrpheanBeholderScryDoubt";
+const MIN_COST: u32 = 4;
+const MAX_COST: u32 = 31;
+const DEFAULT_COST: u32 = 12;
+
+/// The `$2?$` prefix variant a hash was produced with or is expected to
+/// verify against.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Version {
+    /// `$2a$` - the original specification.
+    V2a,
+    /// `$2x$` - crypt_blowfish's buggy-UTF8 compatibility variant.
+    V2x,
+    /// `$2y$` - crypt_blowfish's fixed variant.
+    V2y,
+    /// `$2b$` - the current OpenBSD specification; prefer this for new hashes.
+    V2b,
+}
+
+impl Version {
+    fn tag(self) -> &'static str {
+        match self {
+            Version::V2a => "2a",
+            Version::V2x => "2x",
+            Version::V2y => "2y",
+            Version::V2b => "2b",
+        }
+    }
+
+    fn from_tag(tag: &str) -> Option<Version> {
+        match tag {
+            "2a" => Some(Version::V2a),
+            "2x" => Some(Version::V2x),
+            "2y" => Some(Version::V2y),
+            "2b" => Some(Version::V2b),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Version {
+    fn default() -> Self {
+        Version::V2b
+    }
+}
+
+/// Hashes `password` at the given `cost` (clamped to `4..=31`) under
+/// [`Version::default`], returning the full `$2b$cost$salt+hash` string.
+pub fn bcrypt_hash(password: &[u8], cost: u32) -> String {
+    bcrypt_hash_with_version(password, cost, Version::default())
+}
+
+/// Like [`bcrypt_hash`], but at the crate's recommended default cost
+/// (`12`) for callers with no particular tuning requirement.
+pub fn bcrypt_hash_default(password: &[u8]) -> String {
+    bcrypt_hash(password, DEFAULT_COST)
+}
+
+/// Like [`bcrypt_hash`], but lets the caller pick the `$2?$` tag.
+pub fn bcrypt_hash_with_version(password: &[u8], cost: u32, version: Version) -> String {
+    let cost = cost.clamp(MIN_COST, MAX_COST);
+
+    let mut salt = [0u8; SALT_LEN];
+    crate::randombytes::randombytes_into(&mut salt);
+
+    let hash = eksblowfish_hash(password, &salt, cost);
+    encode(version, cost, &salt, &hash)
+}
+
+/// Verifies `password` against a previously-encoded bcrypt string,
+/// re-running the eksblowfish setup at the embedded cost and comparing
+/// in constant time.
+pub fn bcrypt_verify(encoded: &str, password: &[u8]) -> bool {
+    let Some((_version, cost, salt, expected)) = decode(encoded) else {
+        return false;
+    };
+
+    let actual = eksblowfish_hash(password, &salt, cost);
+    crate::utils::memcmp(&actual, &expected)
+}
+
+fn eksblowfish_hash(password: &[u8], salt: &[u8; SALT_LEN], cost: u32) -> [u8; 23] {
+    let mut key: Vec<u8> = password.to_vec();
+    key.push(0);
+
+    let mut state = Blowfish::new();
+    state.expand_key_with_salt(&key, salt);
+    for _ in 0..(1u64 << cost) {
+        state.expand_key(&key);
+        state.expand_key(salt);
+    }
+
+    let mut words = [0u32; 6];
+    for (word, chunk) in words.iter_mut().zip(CIPHERTEXT.chunks_exact(4)) {
+        *word = u32::from_be_bytes(chunk.try_into().unwrap());
+    }
+
+    for _ in 0..64 {
+        for pair in 0..3 {
+            let (l, r) = state.encrypt_block(words[pair * 2], words[pair * 2 + 1]);
+            words[pair * 2] = l;
+            words[pair * 2 + 1] = r;
+        }
+    }
+
+    let mut out = [0u8; 23];
+    for (word, chunk) in words.iter().zip(out.chunks_mut(4)) {
+        let bytes = word.to_be_bytes();
+        chunk.copy_from_slice(&bytes[..chunk.len()]);
+    }
+    out
+}
+
+fn encode(version: Version, cost: u32, salt: &[u8; SALT_LEN], hash: &[u8; 23]) -> String {
+    format!(
+        "${}${:02}${}{}",
+        version.tag(),
+        cost,
+        b64_encode(salt),
+        b64_encode(hash),
+    )
+}
+
+fn decode(encoded: &str) -> Option<(Version, u32, [u8; SALT_LEN], [u8; 23])> {
+    let rest = encoded.strip_prefix('$')?;
+    let (tag, rest) = rest.split_once('$')?;
+    let version = Version::from_tag(tag)?;
+
+    let (cost, rest) = rest.split_once('$')?;
+    let cost: u32 = cost.parse().ok()?;
+
+    if rest.len() != 53 {
+        return None;
+    }
+    let salt: [u8; SALT_LEN] = b64_decode(&rest[..22])?.try_into().ok()?;
+    let hash: [u8; 23] = b64_decode(&rest[22..])?.try_into().ok()?;
+
+    Some((version, cost, salt, hash))
+}
+
+/// bcrypt's own radix-64 alphabet, distinct from (and ordered differently
+/// than) standard base64.
+const BCRYPT_ALPHABET: &[u8; 64] =
+    b"./ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+fn b64_encode(data: &[u8]) -> String {
+    let mut out = String::new();
+    for chunk in data.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = *chunk.get(1).unwrap_or(&0);
+        let b2 = *chunk.get(2).unwrap_or(&0);
+
+        out.push(BCRYPT_ALPHABET[(b0 >> 2) as usize] as char);
+        out.push(BCRYPT_ALPHABET[(((b0 & 0x03) << 4) | (b1 >> 4)) as usize] as char);
+        if chunk.len() > 1 {
+            out.push(BCRYPT_ALPHABET[(((b1 & 0x0f) << 2) | (b2 >> 6)) as usize] as char);
+        }
+        if chunk.len() > 2 {
+            out.push(BCRYPT_ALPHABET[(b2 & 0x3f) as usize] as char);
+        }
+    }
+    out
+}
+
+fn b64_decode(encoded: &str) -> Option<Vec<u8>> {
+    let lookup = |c: u8| BCRYPT_ALPHABET.iter().position(|&b| b == c).map(|i| i as u8);
+
+    let chars: Vec<u8> = encoded.bytes().collect();
+    let mut out = Vec::with_capacity(chars.len() * 3 / 4);
+
+    for chunk in chars.chunks(4) {
+        let vals: Vec<u8> = chunk.iter().map(|&c| lookup(c)).collect::<Option<_>>()?;
+        out.push((vals[0] << 2) | (vals.get(1).unwrap_or(&0) >> 4));
+        if vals.len() > 2 {
+            out.push((vals[1] << 4) | (vals[2] >> 2));
+        }
+        if vals.len() > 3 {
+            out.push((vals[2] << 6) | vals[3]);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hash_and_verify_round_trip() {
+        let encoded = bcrypt_hash(b"hunter2", 4);
+        assert!(encoded.starts_with("$2b$04$"));
+        assert!(bcrypt_verify(&encoded, b"hunter2"));
+        assert!(!bcrypt_verify(&encoded, b"wrong"));
+    }
+
+    #[test]
+    fn test_clamps_cost() {
+        let encoded = bcrypt_hash(b"hunter2", 1);
+        assert!(encoded.starts_with(&format!("$2b${:02}$", MIN_COST)));
+    }
+
+    #[test]
+    fn test_respects_requested_version() {
+        let encoded = bcrypt_hash_with_version(b"hunter2", 4, Version::V2a);
+        assert!(encoded.starts_with("$2a$04$"));
+        assert!(bcrypt_verify(&encoded, b"hunter2"));
+    }
+
+    #[test]
+    fn test_rejects_garbage() {
+        assert!(!bcrypt_verify("not a bcrypt string", b"hunter2"));
+    }
+}
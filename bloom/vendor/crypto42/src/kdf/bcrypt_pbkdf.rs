@@ -0,0 +1,159 @@
+//! `bcrypt_pbkdf`, the PBKDF2-like construction OpenSSH uses to turn a
+//! passphrase into the key/IV that unlocks a `bcrypt`-format private key.
+//!
+//! Unlike PBKDF2-HMAC (`kdf::pbkdf2`), each round here is a full run of
+//! bcrypt's "eksblowfish" setup rather than a single HMAC call, which is
+//! what makes OpenSSH's `-o` key format resistant to GPU cracking. This
+//! module only implements the KDF; the classic `$2a$`/`$2b$` password
+//! hash format lives in `kdf::bcrypt_hash` (the peer module).
+
+use crate::kdf::blowfish::Blowfish;
+use crate::Error;
+
+const WORD: &[u8; 32] = b"OxychromaticBlowfishSwatDynamite";
+
+/// The inner bcrypt hash core shared by this KDF: an eksblowfish setup
+/// keyed by `hpass`/`hsalt`, re-expanded 64 more times alternating salt
+/// and password, then used to encrypt the fixed constant
+/// `"OxychromaticBlowfishSwatDynamite"` 64 times per 64-bit word-pair.
+fn bcrypt_hash(hpass: &[u8; 64], hsalt: &[u8; 64]) -> [u8; 32] {
+    let mut state = Blowfish::new();
+    state.expand_key_with_salt(hpass, hsalt);
+    for _ in 0..64 {
+        state.expand_key(hsalt);
+        state.expand_key(hpass);
+    }
+
+    let mut words = [0u32; 8];
+    for (word, chunk) in words.iter_mut().zip(WORD.chunks_exact(4)) {
+        *word = u32::from_be_bytes(chunk.try_into().unwrap());
+    }
+
+    for pair in 0..4 {
+        let (mut l, mut r) = (words[pair * 2], words[pair * 2 + 1]);
+        for _ in 0..64 {
+            let (nl, nr) = state.encrypt_block(l, r);
+            l = nl;
+            r = nr;
+        }
+        words[pair * 2] = l;
+        words[pair * 2 + 1] = r;
+    }
+
+    let mut out = [0u8; 32];
+    for (word, chunk) in words.iter().zip(out.chunks_exact_mut(4)) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    out
+}
+
+/// Derives `output.len()` bytes from `password` and `salt`, running
+/// `rounds` stretching iterations of the bcrypt hash core per 32-byte
+/// block (RFC-less, but widely interoperable as OpenSSH's `bcrypt_pbkdf`).
+///
+/// Returns an error if `password` or `salt` is empty, or if
+/// `output.len()` exceeds `1024`.
+pub fn bcrypt_pbkdf(password: &[u8], salt: &[u8], rounds: u32, output: &mut [u8]) -> Result<(), Error> {
+    if password.is_empty() || salt.is_empty() || output.len() > 1024 || rounds == 0 {
+        return Err(Error::Unknown);
+    }
+
+    let hpass = crate::auth::sha512::hash(password);
+    let nblocks = (output.len() + WORD.len() - 1) / WORD.len();
+
+    for b in 1..=nblocks as u32 {
+        let mut salt_block = Vec::with_capacity(salt.len() + 4);
+        salt_block.extend_from_slice(salt);
+        salt_block.extend_from_slice(&b.to_be_bytes());
+
+        let hsalt = crate::auth::sha512::hash(&salt_block);
+        let mut tmpout = bcrypt_hash(&hpass, &hsalt);
+        let mut out = tmpout;
+
+        for _ in 1..rounds {
+            let hsalt = crate::auth::sha512::hash(&tmpout);
+            tmpout = bcrypt_hash(&hpass, &hsalt);
+            for (out_byte, tmp_byte) in out.iter_mut().zip(tmpout.iter()) {
+                *out_byte ^= tmp_byte;
+            }
+        }
+
+        for (i, &byte) in out.iter().enumerate() {
+            let dest = i * nblocks + (b as usize - 1);
+            if dest < output.len() {
+                output[dest] = byte;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rejects_empty_inputs() {
+        let mut out = [0u8; 32];
+        assert!(bcrypt_pbkdf(b"", b"salt", 16, &mut out).is_err());
+        assert!(bcrypt_pbkdf(b"password", b"", 16, &mut out).is_err());
+    }
+
+    #[test]
+    fn test_rejects_oversized_output() {
+        let mut out = vec![0u8; 1025];
+        assert!(bcrypt_pbkdf(b"password", b"salt", 16, &mut out).is_err());
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let mut a = [0u8; 32];
+        let mut b = [0u8; 32];
+        bcrypt_pbkdf(b"password", b"salt", 4, &mut a).unwrap();
+        bcrypt_pbkdf(b"password", b"salt", 4, &mut b).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_different_salts_diverge() {
+        let mut a = [0u8; 32];
+        let mut b = [0u8; 32];
+        bcrypt_pbkdf(b"password", b"salt1", 4, &mut a).unwrap();
+        bcrypt_pbkdf(b"password", b"salt2", 4, &mut b).unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_multiblock_output_is_interleaved_not_repeated() {
+        let mut out = [0u8; 64];
+        bcrypt_pbkdf(b"password", b"salt", 4, &mut out).unwrap();
+        assert_ne!(&out[..32], &out[32..]);
+    }
+
+    /// Known-answer test against a real `ssh-keygen -t ed25519 -a 16`
+    /// key (OpenSSH's default bcrypt-KDF rounds): the 48-byte
+    /// aes256-ctr key+IV derived here must match the one that
+    /// successfully decrypted that key (checkint matched, and the
+    /// plaintext parsed as a valid `ssh-ed25519` private key). This is
+    /// the only test in this module that checks the output against an
+    /// externally produced value; the others check error cases or
+    /// compare outputs with each other.
+    #[test]
+    fn test_kat_matches_openssh_derived_key_iv() {
+        let salt = [
+            0xce, 0x12, 0xb8, 0x1d, 0x3b, 0x9c, 0x6e, 0x5a, 0x2e, 0xa9, 0xb7, 0x7c, 0xdb, 0xcb,
+            0x06, 0x72,
+        ];
+        let expected = [
+            0x5d, 0x0a, 0x80, 0x4e, 0x99, 0xa4, 0x7c, 0xe5, 0xbd, 0x2b, 0x29, 0xb2, 0x51, 0x95,
+            0xef, 0xff, 0x7b, 0x5c, 0x94, 0xbc, 0x62, 0xa7, 0x37, 0x4a, 0x70, 0x45, 0x3b, 0x70,
+            0x5e, 0xa8, 0xb7, 0xe9, 0x1f, 0x31, 0x49, 0xec, 0xd6, 0xf2, 0xb5, 0x68, 0x4f, 0x22,
+            0xfc, 0x4f, 0x1b, 0xf5, 0x00, 0xf0,
+        ];
+
+        let mut out = [0u8; 48];
+        bcrypt_pbkdf(b"testpass123", &salt, 16, &mut out).unwrap();
+        assert_eq!(out, expected);
+    }
+}
@@ -0,0 +1,68 @@
+//! Deriving a cipher-sized key/IV pair from a password in one call.
+//!
+//! Modeled on OpenSSL's `EVP_BytesToKey`, this removes the common footgun
+//! of calling [`argon2id::derive_from_password`] with an arbitrary length
+//! and then manually slicing out the key and IV: the split is defined
+//! once here, so encrypt and decrypt sides agree on it for free.
+
+use crate::kdf::argon2id::{derive_from_password, MemLimit, OpsLimit, Salt};
+use crate::Error;
+
+/// Describes the key and IV/nonce lengths a cipher expects, so
+/// [`derive_key_iv`] knows how much material to derive and how to split
+/// it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CipherDesc {
+    /// Length of the key, in bytes.
+    pub key_len: usize,
+    /// Length of the IV/nonce, in bytes.
+    pub iv_len: usize,
+}
+
+/// A derived key paired with its IV, in the order a cipher expects them.
+#[derive(Clone, Eq, PartialEq)]
+pub struct KeyIvPair {
+    pub key: Vec<u8>,
+    pub iv: Vec<u8>,
+}
+
+/// Derives `cipher.key_len + cipher.iv_len` bytes from `password` via
+/// Argon2id, then splits the output into a [`KeyIvPair`].
+pub fn derive_key_iv(
+    password: &[u8],
+    salt: &Salt,
+    ops: OpsLimit,
+    mem: MemLimit,
+    cipher: CipherDesc,
+) -> Result<KeyIvPair, Error> {
+    let mut material = derive_from_password(cipher.key_len + cipher.iv_len, password, salt, ops, mem)?;
+    let iv = material.split_off(cipher.key_len);
+    Ok(KeyIvPair { key: material, iv })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::kdf::argon2id::gen_salt;
+
+    #[test]
+    fn test_key_iv_lengths() {
+        let salt = gen_salt();
+        let cipher = CipherDesc { key_len: 32, iv_len: 16 };
+        let pair = derive_key_iv(b"password", &salt, OpsLimit(3), MemLimit(1 << 16), cipher).unwrap();
+
+        assert_eq!(pair.key.len(), 32);
+        assert_eq!(pair.iv.len(), 16);
+    }
+
+    #[test]
+    fn test_deterministic_for_same_salt() {
+        let salt = gen_salt();
+        let cipher = CipherDesc { key_len: 32, iv_len: 12 };
+        let a = derive_key_iv(b"password", &salt, OpsLimit(3), MemLimit(1 << 16), cipher).unwrap();
+        let b = derive_key_iv(b"password", &salt, OpsLimit(3), MemLimit(1 << 16), cipher).unwrap();
+
+        assert_eq!(a.key, b.key);
+        assert_eq!(a.iv, b.iv);
+    }
+}
@@ -0,0 +1,235 @@
+//! PBKDF2-HMAC key derivation (RFC 8018) for legacy interop.
+//!
+//! libsodium has no native PBKDF2; this module builds it on top of the
+//! crate's existing HMAC-SHA256/SHA512 primitives so that PKCS#5/OpenSSL
+//! `pbkdf2_hmac`-derived keys, and blobs produced by tools like RAR3, can
+//! be re-derived and verified here. Reach for this module only for
+//! interop with data produced elsewhere; for new designs prefer Argon2id.
+
+use crate::auth::{hmacsha256, hmacsha512};
+use crate::Error;
+
+/// Which HMAC hash backs a PBKDF2 derivation.
+///
+/// `Sha384` is deliberately absent: this crate has no HMAC-SHA384
+/// primitive to build on (libsodium doesn't ship one), and PBKDF2 is only
+/// ever built on top of primitives this crate already exposes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Prf {
+    Sha256,
+    Sha512,
+}
+
+impl Prf {
+    fn hlen(self) -> usize {
+        match self {
+            Prf::Sha256 => hmacsha256::HMACSHA256_BYTES,
+            Prf::Sha512 => hmacsha512::HMACSHA512_BYTES,
+        }
+    }
+
+    fn hmac(self, key: &[u8], data: &[u8]) -> Vec<u8> {
+        match self {
+            Prf::Sha256 => hmacsha256::State::init(key).update(data).finalize().0.to_vec(),
+            Prf::Sha512 => hmacsha512::State::init(key).update(data).finalize().0.to_vec(),
+        }
+    }
+
+    /// The PHC identifier used in the encoded string, e.g. `pbkdf2-sha256`.
+    fn phc_id(self) -> &'static str {
+        match self {
+            Prf::Sha256 => "pbkdf2-sha256",
+            Prf::Sha512 => "pbkdf2-sha512",
+        }
+    }
+
+    fn from_phc_id(id: &str) -> Option<Prf> {
+        match id {
+            "pbkdf2-sha256" => Some(Prf::Sha256),
+            "pbkdf2-sha512" => Some(Prf::Sha512),
+            _ => None,
+        }
+    }
+}
+
+/// Derives a `dk_len`-byte key from `password` and `salt` using the HMAC
+/// hash selected by `prf`.
+///
+/// Returns an error if `iterations` is `0` or `dk_len` is `0`.
+pub fn pbkdf2_hmac(password: &[u8], salt: &[u8], iterations: u32, dk_len: usize, prf: Prf) -> Result<Vec<u8>, Error> {
+    derive(password, salt, iterations, dk_len, prf.hlen(), |key, data| prf.hmac(key, data))
+}
+
+/// Encodes a PBKDF2 derivation as a PHC-style string:
+/// `$pbkdf2-sha256$i=<iterations>$<base64 salt>$<base64 hash>`.
+pub fn hash_password(password: &[u8], salt: &[u8], iterations: u32, dk_len: usize, prf: Prf) -> Result<String, Error> {
+    let dk = pbkdf2_hmac(password, salt, iterations, dk_len, prf)?;
+    Ok(format!(
+        "${}$i={}${}${}",
+        prf.phc_id(),
+        iterations,
+        crate::encoding::base64_encode(salt),
+        crate::encoding::base64_encode(&dk),
+    ))
+}
+
+/// Verifies `password` against a PHC string produced by [`hash_password`].
+pub fn verify_pbkdf2(encoded: &str, password: &[u8]) -> bool {
+    let Some((prf, iterations, salt, expected)) = parse_phc(encoded) else {
+        return false;
+    };
+
+    match pbkdf2_hmac(password, &salt, iterations, expected.len(), prf) {
+        Ok(actual) => crate::utils::memcmp(&actual, &expected),
+        Err(_) => false,
+    }
+}
+
+fn parse_phc(encoded: &str) -> Option<(Prf, u32, Vec<u8>, Vec<u8>)> {
+    let mut parts = encoded.split('$');
+    debug_assert!(parts.next() == Some(""));
+
+    let prf = Prf::from_phc_id(parts.next()?)?;
+    let iterations = parts.next()?.strip_prefix("i=")?.parse().ok()?;
+    let salt = crate::encoding::base64_decode(parts.next()?)?;
+    let hash = crate::encoding::base64_decode(parts.next()?)?;
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Some((prf, iterations, salt, hash))
+}
+
+/// Derives a `dk_len`-byte key from `password` and `salt` using
+/// PBKDF2-HMAC-SHA256.
+///
+/// Returns an error if `iterations` is `0` or `dk_len` is `0`.
+pub fn pbkdf2_hmac_sha256(
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    dk_len: usize,
+) -> Result<Vec<u8>, Error> {
+    pbkdf2_hmac(password, salt, iterations, dk_len, Prf::Sha256)
+}
+
+/// Derives a `dk_len`-byte key from `password` and `salt` using
+/// PBKDF2-HMAC-SHA512.
+///
+/// Returns an error if `iterations` is `0` or `dk_len` is `0`.
+pub fn pbkdf2_hmac_sha512(
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    dk_len: usize,
+) -> Result<Vec<u8>, Error> {
+    pbkdf2_hmac(password, salt, iterations, dk_len, Prf::Sha512)
+}
+
+/// Constant-time comparison of a derived key against an expected one, for
+/// verifying credentials stored elsewhere as PBKDF2 hashes.
+pub fn verify(password: &[u8], salt: &[u8], iterations: u32, expected: &[u8]) -> bool {
+    let derived = match expected.len() {
+        len if len == hmacsha256::HMACSHA256_BYTES => {
+            pbkdf2_hmac_sha256(password, salt, iterations, len)
+        }
+        len => pbkdf2_hmac_sha512(password, salt, iterations, len),
+    };
+
+    match derived {
+        Ok(derived) => crate::utils::memcmp(&derived, expected),
+        Err(_) => false,
+    }
+}
+
+fn derive(
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    dk_len: usize,
+    hlen: usize,
+    hmac: impl Fn(&[u8], &[u8]) -> Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    if iterations == 0 || dk_len == 0 {
+        return Err(Error::Unknown);
+    }
+
+    let block_count = (dk_len + hlen - 1) / hlen;
+    let mut dk = Vec::with_capacity(block_count * hlen);
+
+    for i in 1..=block_count as u32 {
+        let mut salt_block = Vec::with_capacity(salt.len() + 4);
+        salt_block.extend_from_slice(salt);
+        salt_block.extend_from_slice(&i.to_be_bytes());
+
+        let mut u = hmac(password, &salt_block);
+        let mut t = u.clone();
+
+        for _ in 2..=iterations {
+            u = hmac(password, &u);
+            for (t_byte, u_byte) in t.iter_mut().zip(u.iter()) {
+                *t_byte ^= u_byte;
+            }
+        }
+
+        dk.extend_from_slice(&t);
+    }
+
+    dk.truncate(dk_len);
+    Ok(dk)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rejects_zero_iterations() {
+        assert!(pbkdf2_hmac_sha256(b"password", b"salt", 0, 32).is_err());
+    }
+
+    #[test]
+    fn test_rejects_zero_length() {
+        assert!(pbkdf2_hmac_sha256(b"password", b"salt", 1, 0).is_err());
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let a = pbkdf2_hmac_sha256(b"password", b"salt", 100, 32).unwrap();
+        let b = pbkdf2_hmac_sha256(b"password", b"salt", 100, 32).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_different_salts_diverge() {
+        let a = pbkdf2_hmac_sha256(b"password", b"salt1", 100, 32).unwrap();
+        let b = pbkdf2_hmac_sha256(b"password", b"salt2", 100, 32).unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_verify_round_trip() {
+        let dk = pbkdf2_hmac_sha256(b"password", b"salt", 100, 32).unwrap();
+        assert!(verify(b"password", b"salt", 100, &dk));
+        assert!(!verify(b"wrong", b"salt", 100, &dk));
+    }
+
+    #[test]
+    fn test_sha512_round_trip() {
+        let dk = pbkdf2_hmac_sha512(b"password", b"salt", 100, 64).unwrap();
+        assert!(verify(b"password", b"salt", 100, &dk));
+    }
+
+    #[test]
+    fn test_phc_round_trip() {
+        let encoded = hash_password(b"password", b"salt", 100, 32, Prf::Sha256).unwrap();
+        assert!(encoded.starts_with("$pbkdf2-sha256$i=100$"));
+        assert!(verify_pbkdf2(&encoded, b"password"));
+        assert!(!verify_pbkdf2(&encoded, b"wrong"));
+    }
+
+    #[test]
+    fn test_phc_rejects_garbage() {
+        assert!(!verify_pbkdf2("not a phc string", b"password"));
+    }
+}
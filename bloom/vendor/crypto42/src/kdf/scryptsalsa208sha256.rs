@@ -0,0 +1,243 @@
+//! Scrypt is a password-hashing scheme designed to be expensive both in CPU
+//! and memory in order to make brute-force and custom hardware attacks
+//! unrewarding.
+//!
+//! This module binds libsodium's `crypto_pwhash_scryptsalsa208sha256`
+//! family. It exists for interoperability and migration: many existing
+//! systems (and OpenSSL's own `scrypt` KDF, as exposed through `pkcs5`)
+//! store credentials hashed with scrypt, and a server needs to be able to
+//! verify those hashes while transparently re-hashing to Argon2id on the
+//! user's next successful login.
+//!
+//! Prefer the Argon2id module in this crate for new designs; reach for
+//! this one only to interoperate with or migrate away from existing
+//! scrypt-hashed data.
+
+use libsodium_sys::{
+    crypto_pwhash_scryptsalsa208sha256, crypto_pwhash_scryptsalsa208sha256_ll,
+    crypto_pwhash_scryptsalsa208sha256_MEMLIMIT_INTERACTIVE,
+    crypto_pwhash_scryptsalsa208sha256_MEMLIMIT_SENSITIVE,
+    crypto_pwhash_scryptsalsa208sha256_OPSLIMIT_INTERACTIVE,
+    crypto_pwhash_scryptsalsa208sha256_OPSLIMIT_SENSITIVE,
+    crypto_pwhash_scryptsalsa208sha256_SALTBYTES, crypto_pwhash_scryptsalsa208sha256_STRBYTES,
+    crypto_pwhash_scryptsalsa208sha256_STRPREFIX, crypto_pwhash_scryptsalsa208sha256_str,
+    crypto_pwhash_scryptsalsa208sha256_str_verify,
+};
+
+use crate::Error;
+
+/// Number of bytes in a [`Salt`].
+pub const SALTBYTES: usize = crypto_pwhash_scryptsalsa208sha256_SALTBYTES as usize;
+/// Number of bytes in the encoded, NUL-terminated [`HashedPassword`] string.
+pub const STRBYTES: usize = crypto_pwhash_scryptsalsa208sha256_STRBYTES as usize;
+
+/// CPU/memory cost suitable for interactive, online use.
+pub const OPSLIMIT_INTERACTIVE: usize =
+    crypto_pwhash_scryptsalsa208sha256_OPSLIMIT_INTERACTIVE as usize;
+/// Memory cost suitable for interactive, online use.
+pub const MEMLIMIT_INTERACTIVE: usize =
+    crypto_pwhash_scryptsalsa208sha256_MEMLIMIT_INTERACTIVE as usize;
+/// CPU/memory cost for highly sensitive, rarely-derived secrets.
+pub const OPSLIMIT_SENSITIVE: usize =
+    crypto_pwhash_scryptsalsa208sha256_OPSLIMIT_SENSITIVE as usize;
+/// Memory cost for highly sensitive, rarely-derived secrets.
+pub const MEMLIMIT_SENSITIVE: usize =
+    crypto_pwhash_scryptsalsa208sha256_MEMLIMIT_SENSITIVE as usize;
+
+/// A random salt used to derive a key/hash from a password.
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct Salt(pub [u8; SALTBYTES]);
+
+/// The CPU cost parameter, in the same abstract units libsodium uses for
+/// `OPSLIMIT`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OpsLimit(pub usize);
+
+/// The memory cost parameter, in bytes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MemLimit(pub usize);
+
+/// An encoded, self-describing password hash (`$7$...`), as produced by
+/// [`hash_password`].
+#[derive(Clone, Copy)]
+pub struct HashedPassword(pub [u8; STRBYTES]);
+
+/// Generates a random [`Salt`] for use with [`derive_from_password`].
+pub fn gen_salt() -> Salt {
+    let mut salt = [0; SALTBYTES];
+    crate::randombytes::randombytes_into(&mut salt);
+    Salt(salt)
+}
+
+/// Derives an `out_len`-byte key from `password` and `salt`, using the
+/// high-level `ops`/`mem` cost knobs.
+///
+/// Returns an error if `out_len`, `ops` or `mem` are out of the range
+/// accepted by libsodium.
+pub fn derive_from_password(
+    out_len: usize,
+    password: &[u8],
+    salt: &Salt,
+    ops: OpsLimit,
+    mem: MemLimit,
+) -> Result<Vec<u8>, Error> {
+    let mut out = vec![0u8; out_len];
+    let OpsLimit(ops) = ops;
+    let MemLimit(mem) = mem;
+
+    let ret = unsafe {
+        crypto_pwhash_scryptsalsa208sha256(
+            out.as_mut_ptr(),
+            out.len() as u64,
+            password.as_ptr() as *const i8,
+            password.len() as u64,
+            salt.0.as_ptr(),
+            ops as u64,
+            mem,
+        )
+    };
+
+    if ret == 0 {
+        Ok(out)
+    } else {
+        Err(Error::Unknown)
+    }
+}
+
+/// Low-level derivation that takes the explicit scrypt cost parameters
+/// `n`, `r` and `p` instead of libsodium's `ops`/`mem` abstraction.
+///
+/// This is the entry point to use when matching parameters chosen by
+/// another library (e.g. an existing `$7$N$r$p$salt$hash` record), since
+/// `ops`/`mem` cannot always be mapped back to an exact `(N, r, p)`
+/// triple.
+pub fn derive_from_password_ll(
+    password: &[u8],
+    salt: &[u8],
+    n: u64,
+    r: u32,
+    p: u32,
+    out_len: usize,
+) -> Result<Vec<u8>, Error> {
+    let mut out = vec![0u8; out_len];
+
+    let ret = unsafe {
+        crypto_pwhash_scryptsalsa208sha256_ll(
+            password.as_ptr(),
+            password.len(),
+            salt.as_ptr(),
+            salt.len(),
+            n,
+            r,
+            p,
+            out.as_mut_ptr(),
+            out.len(),
+        )
+    };
+
+    if ret == 0 {
+        Ok(out)
+    } else {
+        Err(Error::Unknown)
+    }
+}
+
+/// Hashes `password` with a freshly generated salt, producing an encoded
+/// `$7$...` string suitable for storage.
+pub fn hash_password(
+    password: &[u8],
+    ops: OpsLimit,
+    mem: MemLimit,
+) -> Result<HashedPassword, Error> {
+    let mut out = [0; STRBYTES];
+    let OpsLimit(ops) = ops;
+    let MemLimit(mem) = mem;
+
+    let ret = unsafe {
+        crypto_pwhash_scryptsalsa208sha256_str(
+            out.as_mut_ptr() as *mut i8,
+            password.as_ptr() as *const i8,
+            password.len() as u64,
+            ops as u64,
+            mem,
+        )
+    };
+
+    if ret == 0 {
+        Ok(HashedPassword(out))
+    } else {
+        Err(Error::Unknown)
+    }
+}
+
+/// Verifies `password` against a previously-encoded [`HashedPassword`].
+pub fn verify_password(hashed_password: &HashedPassword, password: &[u8]) -> bool {
+    let ret = unsafe {
+        crypto_pwhash_scryptsalsa208sha256_str_verify(
+            hashed_password.0.as_ptr() as *const i8,
+            password.as_ptr() as *const i8,
+            password.len() as u64,
+        )
+    };
+
+    ret == 0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_verify_password() {
+        use rand;
+
+        for i in 0..32usize {
+            let pw = rand::bytes(i);
+            let pwh = hash_password(&pw, OpsLimit(OPSLIMIT_INTERACTIVE), MemLimit(MEMLIMIT_INTERACTIVE)).unwrap();
+            assert!(verify_password(&pwh, &pw));
+        }
+    }
+
+    #[test]
+    fn test_verify_password_tamper() {
+        use rand;
+
+        for i in 0..16usize {
+            let mut pw = rand::bytes(i);
+            let pwh = hash_password(&pw, OpsLimit(OPSLIMIT_INTERACTIVE), MemLimit(MEMLIMIT_INTERACTIVE)).unwrap();
+            for j in 0..pw.len() {
+                pw[j] ^= 0x20;
+                assert!(!verify_password(&pwh, &pw));
+                pw[j] ^= 0x20;
+            }
+        }
+    }
+
+    #[test]
+    fn test_derive_ll_matches_high_level_for_equivalent_params() {
+        let salt = gen_salt();
+        let out_len = 32;
+
+        let hi = derive_from_password(
+            out_len,
+            b"Correct Horse Battery Staple",
+            &salt,
+            OpsLimit(OPSLIMIT_INTERACTIVE),
+            MemLimit(MEMLIMIT_INTERACTIVE),
+        )
+        .unwrap();
+
+        // N=2^14, r=8, p=1 is what libsodium's interactive preset maps to.
+        let lo = derive_from_password_ll(
+            b"Correct Horse Battery Staple",
+            &salt.0,
+            1 << 14,
+            8,
+            1,
+            out_len,
+        )
+        .unwrap();
+
+        assert_eq!(hi, lo);
+    }
+}
@@ -0,0 +1,169 @@
+//! A variant-agnostic facade over this crate's password-hashing modules.
+//!
+//! The per-algorithm modules (`kdf::argon2id`, `kdf::scryptsalsa208sha256`)
+//! each only verify hashes produced by themselves with matching
+//! parameters. In practice a login flow doesn't know ahead of time which
+//! algorithm or cost parameters produced the hash it has on file — it
+//! just has the encoded PHC string from the last time the user's
+//! password was hashed. This module auto-detects the variant from the
+//! string's prefix and dispatches to the right verifier, and exposes
+//! `needs_rehash` so a caller can transparently upgrade a weakly-hashed
+//! password to the server's current policy after a successful login.
+
+use libsodium_sys::crypto_pwhash_str_verify;
+
+use crate::kdf::argon2;
+use crate::kdf::argon2id::{MemLimit, OpsLimit};
+use crate::kdf::pbkdf2;
+use crate::kdf::scryptsalsa208sha256;
+
+/// Verifies `password` against `encoded`, auto-detecting the algorithm
+/// from its PHC-style prefix (`$argon2id$`, `$argon2i$`, `$argon2d$`,
+/// `$7$`, `$pbkdf2-sha256$`/`$pbkdf2-sha512$`).
+///
+/// Returns `false` for an unrecognized prefix as well as for a genuine
+/// mismatch; callers that need to distinguish "unknown format" from
+/// "wrong password" should inspect the prefix themselves first.
+pub fn verify_password(encoded: &str, password: &[u8]) -> bool {
+    if encoded.starts_with("$argon2id$") || encoded.starts_with("$argon2i$") {
+        // Most Argon2i/id hashes on file came from libsodium's binding;
+        // fall back to the pure-Rust engine for ones produced by
+        // `kdf::argon2` (e.g. explicit-variant hashes from this crate).
+        verify_via_generic_str_verify(encoded, password) || verify_via_pure_argon2(encoded, password)
+    } else if encoded.starts_with("$argon2d$") {
+        // libsodium's generic str_verify doesn't speak Argon2d.
+        verify_via_pure_argon2(encoded, password)
+    } else if encoded.starts_with("$7$") {
+        verify_via_scrypt(encoded, password)
+    } else if encoded.starts_with("$pbkdf2-") {
+        pbkdf2::verify_pbkdf2(encoded, password)
+    } else {
+        false
+    }
+}
+
+fn verify_via_pure_argon2(encoded: &str, password: &[u8]) -> bool {
+    argon2::verify_password(&argon2::HashedPassword(encoded.to_string()), password)
+}
+
+/// Reports whether `encoded`'s embedded cost parameters are weaker than
+/// `target_ops`/`target_mem`/`target_parallelism`, i.e. whether the
+/// caller should re-hash the password with the current policy before the
+/// session ends.
+///
+/// Only understands the Argon2 PHC format (`m=`, `t=`, `p=`); any other
+/// recognized prefix (e.g. scrypt's `$7$` or `$pbkdf2-*$`) is reported as
+/// needing a rehash, since migrating off it onto the crate's preferred
+/// Argon2id is always desirable.
+pub fn needs_rehash(encoded: &str, target_ops: OpsLimit, target_mem: MemLimit, target_parallelism: u32) -> bool {
+    if encoded.starts_with("$7$") || encoded.starts_with("$pbkdf2-") {
+        return true;
+    }
+
+    match parse_argon2_params(encoded) {
+        Some((mem_bytes, ops, lanes)) => {
+            mem_bytes < target_mem.0 || ops < target_ops.0 || lanes < target_parallelism
+        }
+        None => true,
+    }
+}
+
+fn verify_via_generic_str_verify(encoded: &str, password: &[u8]) -> bool {
+    let mut buf = [0i8; 128];
+    let bytes = encoded.as_bytes();
+    if bytes.len() >= buf.len() {
+        return false;
+    }
+    for (dst, &src) in buf.iter_mut().zip(bytes) {
+        *dst = src as i8;
+    }
+
+    let ret = unsafe {
+        crypto_pwhash_str_verify(
+            buf.as_ptr(),
+            password.as_ptr() as *const i8,
+            password.len() as u64,
+        )
+    };
+
+    ret == 0
+}
+
+fn verify_via_scrypt(encoded: &str, password: &[u8]) -> bool {
+    let bytes = encoded.as_bytes();
+    if bytes.len() >= scryptsalsa208sha256::STRBYTES {
+        return false;
+    }
+
+    let mut buf = [0u8; scryptsalsa208sha256::STRBYTES];
+    buf[..bytes.len()].copy_from_slice(bytes);
+    scryptsalsa208sha256::verify_password(&scryptsalsa208sha256::HashedPassword(buf), password)
+}
+
+/// Parses the `m=`/`t=`/`p=` fields out of an Argon2 PHC string,
+/// returning `(memory in bytes, iterations, lanes)`.
+fn parse_argon2_params(encoded: &str) -> Option<(usize, usize, u32)> {
+    let params = encoded.split('$').find(|segment| segment.contains("m="))?;
+
+    let mut mem = None;
+    let mut ops = None;
+    let mut lanes = None;
+    for field in params.split(',') {
+        let (key, value) = field.split_once('=')?;
+        match key {
+            "m" => mem = value.parse::<usize>().ok(),
+            "t" => ops = value.parse::<usize>().ok(),
+            "p" => lanes = value.parse::<u32>().ok(),
+            _ => {}
+        }
+    }
+
+    Some((mem? * 1024, ops?, lanes?))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::kdf::argon2id;
+
+    #[test]
+    fn test_verify_dispatches_to_argon2id() {
+        let pwh = argon2id::hash_password(b"hunter2", OpsLimit(3), MemLimit(1 << 16)).unwrap();
+        let encoded = std::str::from_utf8(&pwh.0)
+            .unwrap()
+            .trim_end_matches('\0')
+            .to_string();
+
+        assert!(verify_password(&encoded, b"hunter2"));
+        assert!(!verify_password(&encoded, b"wrong"));
+    }
+
+    #[test]
+    fn test_unrecognized_prefix_fails_closed() {
+        assert!(!verify_password("$unknown$", b"hunter2"));
+    }
+
+    #[test]
+    fn test_needs_rehash_on_weaker_params() {
+        let encoded = "$argon2id$v=19$m=4096,t=1,p=1$c2FsdHNhbHRzYWx0c2FsdA$aGFzaGhhc2hoYXNoaGFzaA";
+        assert!(needs_rehash(encoded, OpsLimit(3), MemLimit(1 << 20), 1));
+    }
+
+    #[test]
+    fn test_needs_rehash_false_when_already_strong() {
+        let encoded = "$argon2id$v=19$m=1048576,t=4,p=1$c2FsdHNhbHRzYWx0c2FsdA$aGFzaGhhc2hoYXNoaGFzaA";
+        assert!(!needs_rehash(encoded, OpsLimit(3), MemLimit(1 << 20), 1));
+    }
+
+    #[test]
+    fn test_needs_rehash_true_for_scrypt() {
+        assert!(needs_rehash("$7$C6..../....", OpsLimit(3), MemLimit(1 << 16), 1));
+    }
+
+    #[test]
+    fn test_needs_rehash_on_weaker_parallelism() {
+        let encoded = "$argon2id$v=19$m=1048576,t=4,p=1$c2FsdHNhbHRzYWx0c2FsdA$aGFzaGhhc2hoYXNoaGFzaA";
+        assert!(needs_rehash(encoded, OpsLimit(3), MemLimit(1 << 20), 4));
+        assert!(!needs_rehash(encoded, OpsLimit(3), MemLimit(1 << 20), 1));
+    }
+}